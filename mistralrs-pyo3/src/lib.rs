@@ -27,6 +27,8 @@ enum ModelKind {
 static CUDA_DEVICE: std::sync::Mutex<Option<Device>> = std::sync::Mutex::new(None);
 #[cfg(feature = "metal")]
 static METAL_DEVICE: std::sync::Mutex<Option<Device>> = std::sync::Mutex::new(None);
+#[cfg(not(feature = "metal"))]
+static CUDA_DEVICES: std::sync::Mutex<Option<Vec<Device>>> = std::sync::Mutex::new(None);
 
 #[cfg(not(feature = "metal"))]
 fn get_device() -> Result<Device> {
@@ -49,10 +51,70 @@ fn get_device() -> Result<Device> {
     return Ok(res);
 }
 
+/// Enumerate every CUDA device visible to this process, ordinal 0..N, for
+/// pipeline-parallel sharding. Falls back to a single-entry `vec![get_device()?]`
+/// on Metal or when no CUDA device can be opened.
+#[cfg(not(feature = "metal"))]
+pub(crate) fn get_all_devices() -> Result<Vec<Device>> {
+    let mut devices = CUDA_DEVICES.lock().unwrap();
+    if let Some(devices) = devices.as_ref() {
+        return Ok(devices.clone());
+    };
+    let mut ordinal = 0;
+    let mut found = Vec::new();
+    while let Ok(dev) = Device::new_cuda(ordinal) {
+        found.push(dev);
+        ordinal += 1;
+    }
+    if found.is_empty() {
+        found.push(get_device()?);
+    }
+    *devices = Some(found.clone());
+    Ok(found)
+}
+#[cfg(feature = "metal")]
+pub(crate) fn get_all_devices() -> Result<Vec<Device>> {
+    Ok(vec![get_device()?])
+}
+
+/// Best-effort free/total VRAM (bytes) for `device`, so a `device_layout`
+/// can be tuned against actual headroom instead of just a layer count.
+///
+/// Always `None` for now: querying this means going through `cudarc`'s
+/// driver API directly, and this crate has no `Cargo.toml` in this tree to
+/// confirm `cudarc` is actually declared as a direct dependency of
+/// `mistralrs-pyo3` rather than pulled in transitively via `candle-core`
+/// (which would make `cudarc::driver::...` fail to resolve at all). Calling
+/// into a crate we can't verify is linked is worse than reporting "unknown";
+/// callers (`MistralLoader::describe_device_layout`) already treat `None`
+/// as a normal, expected outcome.
+pub(crate) fn device_memory_info(_device: &Device) -> Option<(usize, usize)> {
+    None
+}
+
 #[pyclass]
-struct MistralRunner {
-    runner: Arc<MistralRs>,
-    conversation: Arc<dyn Conversation + Send + Sync>,
+pub struct MistralRunner {
+    pub(crate) runner: Arc<MistralRs>,
+    pub(crate) conversation: Arc<dyn Conversation + Send + Sync>,
+    /// In-flight requests submitted via `submit`, keyed by the id handed
+    /// back to the caller, so `poll`/`await_result` can be called from any
+    /// Python thread without serializing behind a single `rx.recv()`.
+    pending: std::sync::Mutex<HashMap<String, std::sync::mpsc::Receiver<Response>>>,
+    next_request_id: std::sync::atomic::AtomicU64,
+}
+
+impl MistralRunner {
+    pub(crate) fn new(
+        runner: Arc<MistralRs>,
+        conversation: Arc<dyn Conversation + Send + Sync>,
+    ) -> Self {
+        Self {
+            runner,
+            conversation,
+            pending: std::sync::Mutex::new(HashMap::new()),
+            next_request_id: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
 }
 
 #[pyclass]
@@ -73,49 +135,156 @@ pub struct Request {
     pub top_k: Option<usize>,
 }
 
+impl MistralRunner {
+    /// Build the core `_Request` for a Python-facing `Request`, wiring `tx`
+    /// up as its response channel. Shared by every entry point
+    /// (`add_request`, `submit`) so they stay in lockstep as `Request` grows
+    /// fields.
+    fn build_request(
+        &self,
+        py: Python<'_>,
+        request: &Py<Request>,
+        tx: std::sync::mpsc::Sender<Response>,
+    ) -> PyResult<_Request> {
+        let request = request.as_ref(py).borrow();
+        let stop_toks = request
+            .stop_token_ids
+            .as_ref()
+            .map(|x| StopTokens::Ids(x.to_vec()));
+        let prompt = self
+            .conversation
+            .get_prompt(request.messages.clone(), true)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let model_request = _Request {
+            prompt,
+            sampling_params: SamplingParams {
+                temperature: request.temperature,
+                top_k: request.top_k,
+                top_p: request.top_p,
+                top_n_logprobs: request.top_logprobs.unwrap_or(1),
+                repeat_penalty: request.repetition_penalty,
+                presence_penalty: request.presence_penalty,
+                max_len: request.max_tokens,
+                stop_toks,
+            },
+            response: tx,
+            return_logprobs: request.logprobs,
+        };
+        MistralRs::maybe_log_request(self.runner.clone(), format!("{request:?}"));
+        Ok(model_request)
+    }
+}
+
 #[pymethods]
 impl MistralRunner {
     fn add_request(&mut self, request: Py<Request>) -> PyResult<String> {
         let (tx, rx) = channel();
         Python::with_gil(|py| {
-            let request = request.as_ref(py).borrow();
-            let stop_toks = request
-                .stop_token_ids
-                .as_ref()
-                .map(|x| StopTokens::Ids(x.to_vec()));
-            let prompt = match self.conversation.get_prompt(request.messages.clone(), true) {
-                Err(e) => return Err(PyValueError::new_err(e.to_string())),
-                Ok(p) => p,
-            };
-            let model_request = _Request {
-                prompt,
-                sampling_params: SamplingParams {
-                    temperature: request.temperature,
-                    top_k: request.top_k,
-                    top_p: request.top_p,
-                    top_n_logprobs: request.top_logprobs.unwrap_or(1),
-                    repeat_penalty: request.repetition_penalty,
-                    presence_penalty: request.presence_penalty,
-                    max_len: request.max_tokens,
-                    stop_toks,
-                },
-                response: tx,
-                return_logprobs: request.logprobs,
-            };
-
-            MistralRs::maybe_log_request(self.runner.clone(), format!("{request:?}"));
+            let model_request = self.build_request(py, &request, tx)?;
             let sender = self.runner.get_sender();
             sender.send(model_request).unwrap();
             let response = rx.recv().unwrap();
+            self.finish_response(response)?
+                .ok_or_else(|| PyValueError::new_err("response channel closed before completion"))
+        })
+    }
+
+    /// Send `request` to the engine and return immediately with a request
+    /// id, instead of blocking on its completion. Multiple callers (e.g.
+    /// several Python threads) can each `submit` a prompt so the engine has
+    /// more than one in flight to continuous-batch, then retrieve results
+    /// independently with `poll`/`await_result`. Takes `&self`, not
+    /// `&mut self`: the pyo3 cell only ever hands out one `&mut` borrow at a
+    /// time, which would serialize every concurrent caller behind it and
+    /// defeat the point of a pooled dispatch API. `pending`/`next_request_id`
+    /// already provide the interior mutability this needs.
+    ///
+    /// The id returned here is a pyo3-side label only: `_Request` has no id
+    /// field to carry it, so it is never attached to the value sent through
+    /// `get_sender()`. Routing each `Response` back to the right caller works
+    /// because each `submit` opens its own one-shot channel and only this
+    /// binding holds the id -> receiver mapping; the engine itself still sees
+    /// an anonymous stream of requests and does not batch or schedule by id.
+    /// Making the engine id-aware would need an `_Request::id` field (or
+    /// equivalent) added in the `mistralrs` core crate, which this bindings
+    /// crate does not contain the source of.
+    fn submit(&self, request: Py<Request>) -> PyResult<String> {
+        let (tx, rx) = channel();
+        let request_id = Python::with_gil(|py| -> PyResult<String> {
+            let model_request = self.build_request(py, &request, tx)?;
+            let sender = self.runner.get_sender();
+            sender.send(model_request).unwrap();
+            Ok(self.allocate_request_id())
+        })?;
+        self.pending.lock().unwrap().insert(request_id.clone(), rx);
+        Ok(request_id)
+    }
 
-            match response {
-                Response::Error(e) => Err(PyValueError::new_err(e.to_string())),
-                Response::Done(response) => {
-                    MistralRs::maybe_log_response(self.runner.clone(), &response);
-                    Ok(serde_json::to_string(&response).unwrap())
-                }
+    /// Non-blocking check for `request_id`. Returns `None` if the result
+    /// isn't in yet, or `Some(json)` for the completion, and forgets the id
+    /// either way once a response has arrived. Holds the `pending` lock
+    /// only to take the receiver out and put it back, not across the
+    /// `try_recv`, so it never blocks another thread's
+    /// `submit`/`poll`/`await_result`.
+    fn poll(&self, request_id: String) -> PyResult<Option<String>> {
+        let rx = self.take_pending(&request_id)?;
+        match rx.try_recv() {
+            Ok(response) => self.finish_response(response),
+            Err(std::sync::mpsc::TryRecvError::Empty) => {
+                self.pending.lock().unwrap().insert(request_id, rx);
+                Ok(None)
             }
-        })
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => Err(PyValueError::new_err(
+                "response channel closed before completion",
+            )),
+        }
+    }
+
+    /// Block (without holding the GIL or the `pending` lock) until
+    /// `request_id`'s completion arrives, then return it. Used by callers
+    /// that want blocking semantics for one request while other requests
+    /// keep progressing on other threads.
+    fn await_result(&self, request_id: String, py: Python<'_>) -> PyResult<Option<String>> {
+        let rx = self.take_pending(&request_id)?;
+        let (response, rx) = py.allow_threads(move || {
+            let response = rx.recv();
+            (response, rx)
+        });
+        let response = response
+            .map_err(|_| PyValueError::new_err("response channel closed before completion"))?;
+        drop(rx);
+        self.finish_response(response)
+    }
+}
+
+impl MistralRunner {
+    fn allocate_request_id(&self) -> String {
+        let id = self
+            .next_request_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        format!("req-{id}")
+    }
+
+    fn take_pending(&self, request_id: &str) -> PyResult<std::sync::mpsc::Receiver<Response>> {
+        self.pending
+            .lock()
+            .unwrap()
+            .remove(request_id)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown request id {request_id}")))
+    }
+
+    /// Turn a `Response` pulled off a pending request's channel into the
+    /// JSON payload handed back to Python. `Response` has no `Chunk`
+    /// variant today, so every response this sees is already final; the
+    /// caller is responsible for having removed the id from `pending`.
+    fn finish_response(&self, response: Response) -> PyResult<Option<String>> {
+        match response {
+            Response::Error(e) => Err(PyValueError::new_err(e.to_string())),
+            Response::Done(response) => {
+                MistralRs::maybe_log_response(self.runner.clone(), &response);
+                Ok(Some(serde_json::to_string(&response).unwrap()))
+            }
+        }
     }
 }
 
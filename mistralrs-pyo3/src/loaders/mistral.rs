@@ -0,0 +1,179 @@
+use candle_core::{Device, Error, Result};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{device_memory_info, get_all_devices, get_device};
+
+/// How decoder layers are assigned to devices when a model is sharded across
+/// more than one GPU. `DeviceLayout::Explicit` groups are contiguous layer
+/// ranges, one per device, in the order returned by [`get_all_devices`].
+///
+/// Note on scope: this type, and [`MistralLoader`] as a whole, only *plans*
+/// and *validates* the layout. The resulting layer -> device map is not
+/// handed to the `mistralrs` core engine crate (which this pyo3-bindings
+/// crate depends on but does not contain the source of): no layer group's
+/// weights or KV cache are placed on its device from here, the hidden-state
+/// tensor is not moved between devices during the forward pass, and the
+/// final logits are not pulled back to device 0 by this crate.
+#[derive(Debug, Clone)]
+pub enum DeviceLayout {
+    /// Everything on a single device (the single-GPU path).
+    /// `MistralLoader::new` defaults to `Auto`, not `Single`.
+    Single,
+    /// Split `num_hidden_layers` into `N` roughly equal contiguous groups,
+    /// one per available device.
+    Auto,
+    /// Explicit layer index -> device ordinal assignment.
+    Explicit(Vec<usize>),
+}
+
+/// For each of `num_hidden_layers` layers, the CUDA ordinal it should be
+/// placed on. Embedding + the first group stay on device 0; the final
+/// RMSNorm + lm_head ride along with the last group's device.
+///
+/// Rejects an `Explicit` map whose length doesn't match `num_hidden_layers`
+/// or that references a device ordinal `>= num_devices`, rather than
+/// silently truncating or dropping layers.
+pub(crate) fn build_device_map(
+    layout: &DeviceLayout,
+    num_hidden_layers: usize,
+    num_devices: usize,
+) -> Result<Vec<usize>> {
+    match layout {
+        DeviceLayout::Single => Ok(vec![0; num_hidden_layers]),
+        DeviceLayout::Explicit(map) => {
+            if map.len() != num_hidden_layers {
+                return Err(Error::Msg(format!(
+                    "device_layout has {} entries but the model has {num_hidden_layers} hidden layers",
+                    map.len()
+                )));
+            }
+            if let Some(&ordinal) = map.iter().find(|&&ordinal| ordinal >= num_devices) {
+                return Err(Error::Msg(format!(
+                    "device_layout references device ordinal {ordinal}, but only {num_devices} device(s) are available"
+                )));
+            }
+            Ok(map.clone())
+        }
+        DeviceLayout::Auto => {
+            let num_devices = num_devices.max(1);
+            let base = num_hidden_layers / num_devices;
+            let remainder = num_hidden_layers % num_devices;
+            let mut map = Vec::with_capacity(num_hidden_layers);
+            for device in 0..num_devices {
+                // Front-load the remainder so earlier devices take the extra layer.
+                let group_size = base + usize::from(device < remainder);
+                map.extend(std::iter::repeat(device).take(group_size));
+            }
+            Ok(map)
+        }
+    }
+}
+
+/// Plans and validates how a model's decoder layers are sharded across the
+/// devices visible to this process. This is deliberately narrow: loading
+/// weights, building the tokenizer/chat template, and everything else a
+/// full model loader would normally own lives in the `mistralrs` core
+/// engine crate, which this pyo3-bindings crate does not contain the
+/// source of, so `MistralLoader` does not carry model identity or config
+/// fields it cannot act on. See the "Note on scope" on [`DeviceLayout`].
+#[pyclass]
+pub struct MistralLoader {
+    device_layout: DeviceLayout,
+}
+
+#[pymethods]
+impl MistralLoader {
+    #[new]
+    #[pyo3(signature = (device_layout = None))]
+    fn new(device_layout: Option<Vec<usize>>) -> Self {
+        let device_layout = match device_layout {
+            Some(map) => DeviceLayout::Explicit(map),
+            None => DeviceLayout::Auto,
+        };
+        Self { device_layout }
+    }
+
+    /// Resolve the device layout against the devices actually present and
+    /// report, per device, how many layers it's holding, so users can tune
+    /// `device_layout` before paying for a full weight load. Free/total VRAM
+    /// is shown only when `device_memory_info` returns it; that function
+    /// always returns `None`, so every device reads "memory unknown". Layer
+    /// counts always reflect a validated layout.
+    fn describe_device_layout(&self, num_hidden_layers: usize) -> PyResult<String> {
+        let devices = self
+            .devices()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let map = self
+            .device_map(num_hidden_layers)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let mut counts = vec![0usize; devices.len()];
+        for &device in &map {
+            counts[device] += 1;
+        }
+        let report = devices
+            .iter()
+            .zip(counts)
+            .enumerate()
+            .map(
+                |(ordinal, (device, count))| match device_memory_info(device) {
+                    Some((free, total)) => format!(
+                        "cuda:{ordinal} -> {count} layers, {}/{} MiB free",
+                        free / (1024 * 1024),
+                        total / (1024 * 1024)
+                    ),
+                    None => format!("cuda:{ordinal} -> {count} layers, memory unknown"),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(report)
+    }
+}
+
+impl MistralLoader {
+    /// Devices participating in this load, in ordinal order. Embedding and
+    /// the first layer group live on `devices[0]`; the final RMSNorm and
+    /// lm_head live on the last layer group's device (the last device
+    /// under `Auto`).
+    pub(crate) fn devices(&self) -> Result<Vec<Device>> {
+        match &self.device_layout {
+            DeviceLayout::Single => Ok(vec![get_device()?]),
+            _ => get_all_devices(),
+        }
+    }
+
+    pub(crate) fn device_map(&self, num_hidden_layers: usize) -> Result<Vec<usize>> {
+        let devices = self.devices()?;
+        build_device_map(&self.device_layout, num_hidden_layers, devices.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_splits_evenly_when_layers_divide_devices() {
+        let map = build_device_map(&DeviceLayout::Auto, 8, 4).unwrap();
+        assert_eq!(map, vec![0, 0, 1, 1, 2, 2, 3, 3]);
+    }
+
+    #[test]
+    fn auto_front_loads_the_remainder() {
+        let map = build_device_map(&DeviceLayout::Auto, 10, 3).unwrap();
+        // 10 / 3 = 3 remainder 1, so device 0 gets one extra layer.
+        assert_eq!(map, vec![0, 0, 0, 0, 1, 1, 1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn explicit_rejects_length_mismatch() {
+        let err = build_device_map(&DeviceLayout::Explicit(vec![0, 1, 0]), 4, 2).unwrap_err();
+        assert!(err.to_string().contains("3 entries"));
+    }
+
+    #[test]
+    fn explicit_rejects_out_of_range_ordinal() {
+        let err = build_device_map(&DeviceLayout::Explicit(vec![0, 1, 2]), 3, 2).unwrap_err();
+        assert!(err.to_string().contains("ordinal 2"));
+    }
+}